@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Iter as HashMapIter;
+use std::hash::Hash;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use Commute;
+
+/// A commutative data structure for exact frequency counts.
+#[deriving(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Frequencies<T> {
+    data: HashMap<T, u64>,
+}
+
+impl<T: Eq + Hash> Frequencies<T> {
+    /// Create a new frequency table with no samples.
+    pub fn new() -> Frequencies<T> {
+        Frequencies { data: HashMap::new() }
+    }
+
+    /// Add a sample to the frequency table.
+    pub fn add(&mut self, v: T) {
+        match self.data.get_mut(&v) {
+            Some(count) => { *count += 1; return; }
+            None => {}
+        }
+        self.data.insert(v, 1);
+    }
+
+    /// Return the number of samples added to the frequency table.
+    pub fn len(&self) -> u64 {
+        self.data.values().fold(0u64, |a, &b| a + b)
+    }
+
+    /// Returns `true` if and only if no samples have been added.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of times `v` has been seen.
+    pub fn count(&self, v: &T) -> u64 {
+        self.data.get(v).map(|&count| count).unwrap_or(0)
+    }
+
+    /// Returns the number of distinct values that have been seen.
+    pub fn cardinality(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// Returns the values that have been seen exactly once.
+    ///
+    /// This is useful for column-profiling tasks like detecting candidate
+    /// primary keys.
+    pub fn unique(&self) -> Vec<&T> {
+        self.data.iter()
+            .filter(|&(_, &count)| count == 1)
+            .map(|(v, _)| v)
+            .collect()
+    }
+
+    /// Returns the `(value, count)` pairs of the frequency table, sorted
+    /// in descending order of count. The order of values with equal
+    /// counts is not defined.
+    pub fn most_frequent(&self) -> Vec<(&T, u64)> {
+        let mut counts: Vec<(&T, u64)> = self.data.iter()
+            .map(|(v, &count)| (v, count))
+            .collect();
+        counts.sort_by(|&(_, c1), &(_, c2)| c2.cmp(&c1));
+        counts
+    }
+
+    /// Returns an iterator over `(value, count)` pairs.
+    pub fn iter(&self) -> HashMapIter<T, u64> {
+        self.data.iter()
+    }
+}
+
+impl<T: Eq + Hash> Commute for Frequencies<T> {
+    fn merge(&mut self, other: Frequencies<T>) {
+        for (v, count) in other.data.into_iter() {
+            match self.data.get_mut(&v) {
+                Some(c) => { *c += count; continue; }
+                None => {}
+            }
+            self.data.insert(v, count);
+        }
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Frequencies<T> {
+    fn from_iter<I: Iterator<T>>(it: I) -> Frequencies<T> {
+        let mut v = Frequencies::new();
+        v.extend(it);
+        v
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Frequencies<T> {
+    fn extend<I: Iterator<T>>(&mut self, mut it: I) {
+        for sample in it {
+            self.add(sample);
+        }
+    }
+}
+
+impl<T: Eq + Hash> Default for Frequencies<T> {
+    fn default() -> Frequencies<T> {
+        Frequencies::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Frequencies;
+
+    #[test]
+    fn cardinality_and_unique_empty() {
+        let v: Frequencies<uint> = vec![].into_iter().collect();
+        assert_eq!(v.cardinality(), 0);
+        assert_eq!(v.unique(), Vec::<&uint>::new());
+    }
+
+    #[test]
+    fn cardinality_counts_distinct_values() {
+        let v: Frequencies<uint> = vec![1u, 1, 2, 3, 3, 3].into_iter().collect();
+        assert_eq!(v.cardinality(), 3);
+    }
+
+    #[test]
+    fn unique_finds_singleton_values() {
+        let v: Frequencies<uint> = vec![1u, 1, 2, 3, 3, 3].into_iter().collect();
+        let mut unique: Vec<uint> = v.unique().into_iter().map(|&x| x).collect();
+        unique.sort();
+        assert_eq!(unique, vec![2]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip() {
+        let v: Frequencies<uint> = vec![1u, 1, 2, 3, 3, 3].into_iter().collect();
+        let encoded = ::serde_json::to_string(&v).unwrap();
+        let decoded: Frequencies<uint> =
+            ::serde_json::from_str(encoded.as_slice()).unwrap();
+        assert_eq!(decoded.cardinality(), v.cardinality());
+        assert_eq!(decoded.count(&1u), v.count(&1u));
+        assert_eq!(decoded.count(&3u), v.count(&3u));
+    }
+}
@@ -1,7 +1,16 @@
 #![experimental]
 #![feature(default_type_params, slicing_syntax)]
 
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
 use std::hash;
+use std::mem;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 pub use frequency::Frequencies;
 pub use minmax::MinMax;
@@ -14,6 +23,7 @@ pub use unsorted::{Unsorted, median, mode};
 /// This allows types like `f64` to be used in data structures that require
 /// `Ord`. When an ordering is not defined, an arbitrary order is returned.
 #[deriving(Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct Partial<T>(pub T);
 
 impl<T: PartialEq> Eq for Partial<T> {}
@@ -71,6 +81,57 @@ pub fn merge_all<T: Commute, I: Iterator<T>>(mut it: I) -> Option<T> {
     }
 }
 
+/// Merges all items in `data` using up to `nthreads` worker threads.
+///
+/// `data` is split into `nthreads` roughly-equal chunks. Each chunk is
+/// built from a chunk of the raw input by `build` (e.g. looping
+/// `Default::default()` through `extend`/`consume`) on a separate
+/// thread, and the resulting partial accumulators are folded together
+/// with `merge_all` on the calling thread. Because `Commute` is
+/// associative and `Default` is its identity, the result is the same as
+/// building one accumulator from `data` sequentially, regardless of
+/// where the chunk boundaries happen to fall. The partials are merged in
+/// the order the threads finish, so floating-point accumulators such as
+/// `OnlineStats` agree with the sequential result only up to rounding.
+///
+/// This parallelizes the expensive part of the job: turning millions of
+/// raw samples `S` (which generally aren't `Commute` themselves, e.g. a
+/// raw `f64` or a raw `T` fed to `Frequencies`/`Unsorted`) into a partial
+/// accumulator. Only the cheap final merge of the (few) partials runs
+/// sequentially.
+///
+/// If `data` is empty, `None` is returned.
+pub fn merge_all_parallel<S: Send, T: Commute + Send>(
+    data: Vec<S>,
+    nthreads: uint,
+    build: fn(Vec<S>) -> T,
+) -> Option<T> {
+    if data.is_empty() {
+        return None;
+    }
+    let nthreads = if nthreads == 0 { 1 } else { nthreads };
+    let chunk_size = (data.len() + nthreads - 1) / nthreads;
+
+    let (tx, rx) = channel();
+    let mut njobs = 0u;
+    let mut data = data;
+    while !data.is_empty() {
+        let rest = if data.len() > chunk_size {
+            data.split_off(chunk_size)
+        } else {
+            Vec::new()
+        };
+        let chunk = mem::replace(&mut data, rest);
+        let tx = tx.clone();
+        njobs += 1;
+        spawn(proc() {
+            tx.send(build(chunk));
+        });
+    }
+    drop(tx);
+    merge_all(range(0u, njobs).map(|_| rx.recv()))
+}
+
 impl<T: Commute> Commute for Option<T> {
     fn merge(&mut self, other: Option<T>) {
         match self {
@@ -131,4 +192,73 @@ mod test {
         merged.merge(Some(v2));
         assert_eq!(merged.unwrap().mode(), Some(5));
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip_online_stats() {
+        use online::OnlineStats;
+
+        let mut stats = OnlineStats::new();
+        stats.extend(vec![1.0f64, 2.0, 3.0].into_iter());
+
+        let encoded = ::serde_json::to_string(&stats).unwrap();
+        let decoded: OnlineStats =
+            ::serde_json::from_str(encoded.as_slice()).unwrap();
+        assert_eq!(decoded.mean(), stats.mean());
+        assert_eq!(decoded.len(), stats.len());
+    }
+
+    fn build_online_stats(chunk: Vec<f64>) -> ::online::OnlineStats {
+        chunk.into_iter().collect()
+    }
+
+    #[test]
+    fn merge_all_parallel_matches_sequential() {
+        use online::OnlineStats;
+        use merge_all_parallel;
+
+        let data: Vec<f64> = range(0u, 997).map(|i| i as f64).collect();
+
+        let sequential: OnlineStats = data.clone().into_iter().collect();
+        let parallel = merge_all_parallel(
+            data.clone(), 8u, build_online_stats,
+        ).unwrap();
+
+        // Partial accumulators are folded in whatever order `rx.recv()`
+        // hands them back, which races across threads. The result is
+        // identical up to floating-point non-associativity, so compare
+        // with a tolerance rather than bit-for-bit.
+        assert_eq!(parallel.len(), sequential.len());
+        assert!((parallel.mean() - sequential.mean()).abs() < 1e-6);
+        assert!((parallel.variance() - sequential.variance()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn merge_all_parallel_empty_is_none() {
+        use merge_all_parallel;
+
+        let data: Vec<f64> = vec![];
+        let result = merge_all_parallel(data, 4u, build_online_stats);
+        assert!(result.is_none());
+    }
+
+    fn build_unsorted(chunk: Vec<uint>) -> ::unsorted::Unsorted<uint> {
+        chunk.into_iter().collect()
+    }
+
+    #[test]
+    fn merge_all_parallel_matches_sequential_unsorted() {
+        use unsorted::Unsorted;
+        use merge_all_parallel;
+
+        let data: Vec<uint> = range(0u, 997).map(|i| i % 50).collect();
+
+        let mut sequential: Unsorted<uint> = data.clone().into_iter().collect();
+        let mut parallel: Unsorted<uint> =
+            merge_all_parallel(data.clone(), 8u, build_unsorted).unwrap();
+
+        assert_eq!(parallel.len(), sequential.len());
+        assert_eq!(parallel.median(), sequential.median());
+        assert_eq!(parallel.modes(), sequential.modes());
+    }
 }
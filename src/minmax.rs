@@ -0,0 +1,99 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use Commute;
+
+/// A commutative data structure for tracking minimum and maximum values.
+#[deriving(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MinMax<T> {
+    len: uint,
+    min: Option<T>,
+    max: Option<T>,
+}
+
+impl<T: PartialOrd + Clone> MinMax<T> {
+    /// Create a new `MinMax` with no samples.
+    pub fn new() -> MinMax<T> {
+        MinMax { len: 0, min: None, max: None }
+    }
+
+    /// Add a sample to the data.
+    pub fn add(&mut self, v: T) {
+        self.len += 1;
+        if self.min.is_none() || v < *self.min.as_ref().unwrap() {
+            self.min = Some(v.clone());
+        }
+        if self.max.is_none() || v > *self.max.as_ref().unwrap() {
+            self.max = Some(v);
+        }
+    }
+
+    /// Returns the number of samples added.
+    pub fn len(&self) -> uint {
+        self.len
+    }
+
+    /// Returns `true` if and only if no samples have been added.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the minimum of the data set seen so far.
+    ///
+    /// `None` is returned if no samples have been added.
+    pub fn min(&self) -> Option<&T> {
+        self.min.as_ref()
+    }
+
+    /// Returns the maximum of the data set seen so far.
+    ///
+    /// `None` is returned if no samples have been added.
+    pub fn max(&self) -> Option<&T> {
+        self.max.as_ref()
+    }
+}
+
+impl<T: PartialOrd + Clone> Commute for MinMax<T> {
+    fn merge(&mut self, other: MinMax<T>) {
+        self.len += other.len;
+        match other.min {
+            None => {}
+            Some(v) => {
+                if self.min.is_none() || v < *self.min.as_ref().unwrap() {
+                    self.min = Some(v);
+                }
+            }
+        }
+        match other.max {
+            None => {}
+            Some(v) => {
+                if self.max.is_none() || v > *self.max.as_ref().unwrap() {
+                    self.max = Some(v);
+                }
+            }
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> FromIterator<T> for MinMax<T> {
+    fn from_iter<I: Iterator<T>>(it: I) -> MinMax<T> {
+        let mut v = MinMax::new();
+        v.extend(it);
+        v
+    }
+}
+
+impl<T: PartialOrd + Clone> Extend<T> for MinMax<T> {
+    fn extend<I: Iterator<T>>(&mut self, mut it: I) {
+        for sample in it {
+            self.add(sample);
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> Default for MinMax<T> {
+    fn default() -> MinMax<T> {
+        MinMax::new()
+    }
+}
@@ -0,0 +1,115 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use Commute;
+
+/// Compute the mean of a stream of data.
+pub fn mean<I: Iterator<f64>>(it: I) -> f64 {
+    let mut v: OnlineStats = it.collect();
+    v.mean()
+}
+
+/// Compute the variance of a stream of data.
+pub fn variance<I: Iterator<f64>>(it: I) -> f64 {
+    let mut v: OnlineStats = it.collect();
+    v.variance()
+}
+
+/// Compute the standard deviation of a stream of data.
+pub fn stddev<I: Iterator<f64>>(it: I) -> f64 {
+    let mut v: OnlineStats = it.collect();
+    v.stddev()
+}
+
+/// A commutative data structure for computing the mean, variance and
+/// standard deviation of a stream of data, using Welford's online
+/// algorithm.
+#[deriving(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OnlineStats {
+    size: uint,
+    mean: f64,
+    q: f64,
+}
+
+impl OnlineStats {
+    /// Create a new `OnlineStats` accumulator with no samples.
+    pub fn new() -> OnlineStats {
+        OnlineStats { size: 0, mean: 0.0, q: 0.0 }
+    }
+
+    /// Add a new sample.
+    pub fn add(&mut self, v: f64) {
+        let oldmean = self.mean;
+        self.size += 1;
+        self.mean += (v - oldmean) / (self.size as f64);
+        self.q += (v - oldmean) * (v - self.mean);
+    }
+
+    /// Returns the number of samples seen.
+    pub fn len(&self) -> uint {
+        self.size
+    }
+
+    /// Returns the mean of the samples seen so far.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the sample variance of the samples seen so far.
+    pub fn variance(&self) -> f64 {
+        if self.size <= 1 {
+            return 0.0;
+        }
+        self.q / ((self.size - 1) as f64)
+    }
+
+    /// Returns the sample standard deviation of the samples seen so far.
+    pub fn stddev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+impl Commute for OnlineStats {
+    fn merge(&mut self, other: OnlineStats) {
+        if other.size == 0 {
+            return;
+        }
+        if self.size == 0 {
+            *self = other;
+            return;
+        }
+        let newsize = self.size + other.size;
+        let delta = other.mean - self.mean;
+        let newmean =
+            self.mean + delta * (other.size as f64) / (newsize as f64);
+        let newq = self.q + other.q
+            + (delta * delta) * (self.size as f64) * (other.size as f64)
+              / (newsize as f64);
+        self.size = newsize;
+        self.mean = newmean;
+        self.q = newq;
+    }
+}
+
+impl FromIterator<f64> for OnlineStats {
+    fn from_iter<I: Iterator<f64>>(it: I) -> OnlineStats {
+        let mut v = OnlineStats::new();
+        v.extend(it);
+        v
+    }
+}
+
+impl Extend<f64> for OnlineStats {
+    fn extend<I: Iterator<f64>>(&mut self, mut it: I) {
+        for sample in it {
+            self.add(sample);
+        }
+    }
+}
+
+impl Default for OnlineStats {
+    fn default() -> OnlineStats {
+        OnlineStats::new()
+    }
+}
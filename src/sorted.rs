@@ -0,0 +1,123 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use Commute;
+
+/// A commutative data structure that keeps its samples in sorted order.
+///
+/// Unlike `Unsorted`, the sort is maintained incrementally as samples are
+/// added, which makes `merge` more expensive but queries always "hot".
+#[deriving(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Sorted<T> {
+    data: Vec<T>,
+}
+
+impl<T: PartialOrd> Sorted<T> {
+    /// Create a new `Sorted` buffer with no samples.
+    pub fn new() -> Sorted<T> {
+        Sorted { data: Vec::new() }
+    }
+
+    /// Add a new sample, inserting it in sorted position.
+    pub fn add(&mut self, v: T) {
+        let pos = self.data.iter().position(|x| *x > v)
+            .unwrap_or(self.data.len());
+        self.data.insert(pos, v);
+    }
+
+    /// Returns the number of samples.
+    pub fn len(&self) -> uint {
+        self.data.len()
+    }
+
+    /// Returns `true` if and only if there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the samples, in sorted order.
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+}
+
+impl<T: PartialOrd + Clone> Sorted<T> {
+    /// Returns the mode of the data.
+    ///
+    /// This is a singular value, even if there are ties for the most
+    /// frequent value.
+    pub fn mode(&self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let mut best = &self.data[0];
+        let mut best_count = 1u;
+        let mut cur = &self.data[0];
+        let mut cur_count = 1u;
+        for v in self.data[1..].iter() {
+            if *v == *cur {
+                cur_count += 1;
+            } else {
+                cur = v;
+                cur_count = 1;
+            }
+            if cur_count > best_count {
+                best = cur;
+                best_count = cur_count;
+            }
+        }
+        Some(best.clone())
+    }
+}
+
+impl<T: PartialOrd> Commute for Sorted<T> {
+    fn merge(&mut self, other: Sorted<T>) {
+        let mut merged = Vec::with_capacity(self.data.len() + other.data.len());
+        let mut xs = self.data.drain();
+        let mut ys = other.data.into_iter();
+        let mut x = xs.next();
+        let mut y = ys.next();
+        loop {
+            match (x, y) {
+                (Some(xv), Some(yv)) => {
+                    if xv <= yv {
+                        merged.push(xv);
+                        x = xs.next();
+                        y = Some(yv);
+                    } else {
+                        merged.push(yv);
+                        x = Some(xv);
+                        y = ys.next();
+                    }
+                }
+                (Some(xv), None) => { merged.push(xv); x = xs.next(); }
+                (None, Some(yv)) => { merged.push(yv); y = ys.next(); }
+                (None, None) => break,
+            }
+        }
+        self.data = merged;
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for Sorted<T> {
+    fn from_iter<I: Iterator<T>>(it: I) -> Sorted<T> {
+        let mut v = Sorted::new();
+        v.extend(it);
+        v
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for Sorted<T> {
+    fn extend<I: Iterator<T>>(&mut self, mut it: I) {
+        for sample in it {
+            self.add(sample);
+        }
+    }
+}
+
+impl<T: PartialOrd> Default for Sorted<T> {
+    fn default() -> Sorted<T> {
+        Sorted::new()
+    }
+}
@@ -0,0 +1,394 @@
+use std::mem;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use {Commute, Partial};
+
+/// Compute the exact median on a stream of data.
+///
+/// (This has time complexity `O(nlogn)` and space complexity `O(n)`.)
+pub fn median<T: PartialOrd + ToPrimitive, I: Iterator<T>>(it: I) -> Option<f64> {
+    let mut v: Unsorted<T> = it.collect();
+    v.median()
+}
+
+/// Compute the mode on a stream of data.
+///
+/// (This has time complexity `O(nlogn)` and space complexity `O(n)`.)
+pub fn mode<T: PartialOrd + Clone, I: Iterator<T>>(it: I) -> Option<T> {
+    let mut v: Unsorted<T> = it.collect();
+    v.mode()
+}
+
+/// A commutative data structure for lazily sorted sequences of data.
+///
+/// The sort does not occur until statistics need to be computed.
+///
+/// Note that this works on types that do not define a total ordering like
+/// `f32` and `f64`. When an ordering is not defined, an arbitrary order
+/// is returned.
+#[deriving(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Unsorted<T> {
+    sorted: bool,
+    data: Vec<Partial<T>>,
+}
+
+impl<T: PartialOrd> Unsorted<T> {
+    /// Create a new empty `Unsorted` buffer.
+    pub fn new() -> Unsorted<T> {
+        Unsorted { sorted: false, data: Vec::new() }
+    }
+
+    /// Add a new element to the buffer.
+    pub fn add(&mut self, v: T) {
+        self.dirtied();
+        self.data.push(Partial(v));
+    }
+
+    /// Returns the number of data points.
+    pub fn len(&self) -> uint {
+        self.data.len()
+    }
+
+    /// Returns `true` if and only if there are no data points.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn dirtied(&mut self) {
+        self.sorted = false;
+    }
+
+    fn sort(&mut self) {
+        if !self.sorted {
+            self.data.sort();
+            self.sorted = true;
+        }
+    }
+}
+
+impl<T: PartialOrd + ToPrimitive> Unsorted<T> {
+    /// Returns the median of the data.
+    ///
+    /// This has time complexity `O(nlogn)`.
+    ///
+    /// If the number of data points is even, then the median is the average
+    /// of the two middle values.
+    pub fn median(&mut self) -> Option<f64> {
+        self.sort();
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(median_on_sorted(self.data.as_slice()))
+    }
+
+    /// Returns the quartiles `(Q1, Q2, Q3)` of the data using Tukey's
+    /// method (the "hinges").
+    ///
+    /// `Q2` is simply the median. `Q1` and `Q3` are the medians of the
+    /// lower and upper halves of the data, respectively. When the number
+    /// of data points is odd, the median element itself is included in
+    /// both halves.
+    ///
+    /// Returns `None` when there is no data.
+    pub fn quartiles(&mut self) -> Option<(f64, f64, f64)> {
+        self.sort();
+        let n = self.data.len();
+        if n == 0 {
+            return None;
+        }
+        if n == 1 {
+            let v = self.data[0].0.to_f64().unwrap();
+            return Some((v, v, v));
+        }
+        if n == 2 {
+            let lo = self.data[0].0.to_f64().unwrap();
+            let hi = self.data[1].0.to_f64().unwrap();
+            let mid = (lo + hi) / 2.0;
+            return Some((lo, mid, hi));
+        }
+
+        let data = self.data.as_slice();
+        let q2 = median_on_sorted(data);
+        let lower_end = if n % 2 == 0 { n / 2 } else { (n + 1) / 2 };
+        let upper_start = n / 2;
+        let q1 = median_on_sorted(data[..lower_end]);
+        let q3 = median_on_sorted(data[upper_start..]);
+        Some((q1, q2, q3))
+    }
+
+    /// Returns the `p`th percentile of the data, where `p` is between
+    /// `0.0` and `100.0` inclusive.
+    ///
+    /// This is its own quantile estimator (linear interpolation between
+    /// the two nearest ranks in the sorted buffer, a la NIST method 7)
+    /// and is not defined in terms of `quartiles`'s Tukey hinges. Like
+    /// most statistics libraries that offer both, the two are allowed to
+    /// disagree at `p == 25.0`/`50.0`/`75.0`.
+    ///
+    /// Returns `None` when there is no data.
+    pub fn percentile(&mut self, p: f64) -> Option<f64> {
+        self.sort();
+        if self.data.is_empty() {
+            return None;
+        }
+        let data = self.data.as_slice();
+        let n = data.len();
+        match p {
+            p if p <= 0.0 => Some(data[0].0.to_f64().unwrap()),
+            p if p >= 100.0 => Some(data[n - 1].0.to_f64().unwrap()),
+            p => {
+                let rank = (p / 100.0) * ((n - 1) as f64);
+                let lo = rank.floor() as uint;
+                let hi = rank.ceil() as uint;
+                let lo_val = data[lo].0.to_f64().unwrap();
+                let hi_val = data[hi].0.to_f64().unwrap();
+                Some(lo_val + (hi_val - lo_val) * (rank - rank.floor()))
+            }
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> Unsorted<T> {
+    /// Returns the mode of the data.
+    ///
+    /// Returns `None` when there is a tie for the most frequent value, or
+    /// when there is no data. Use `modes` to retrieve every value tied
+    /// for the highest frequency.
+    pub fn mode(&mut self) -> Option<T> {
+        let counts = self.counts();
+        if counts.is_empty() {
+            return None;
+        }
+        let max = counts[0].1;
+        if counts.len() > 1 && counts[1].1 == max {
+            return None;
+        }
+        Some(counts[0].0.clone())
+    }
+
+    /// Returns every value tied for the highest frequency.
+    ///
+    /// Returns an empty vector when there is no data.
+    pub fn modes(&mut self) -> Vec<T> {
+        let counts = self.counts();
+        if counts.is_empty() {
+            return vec![];
+        }
+        let max = counts[0].1;
+        counts.into_iter()
+              .take_while(|&(_, count)| count == max)
+              .map(|(v, _)| v)
+              .collect()
+    }
+
+    /// Returns every value tied for the lowest frequency.
+    ///
+    /// Returns an empty vector when there is no data.
+    pub fn antimodes(&mut self) -> Vec<T> {
+        let mut counts = self.counts();
+        if counts.is_empty() {
+            return vec![];
+        }
+        counts.sort_by(|a, b| a.1.cmp(&b.1));
+        let min = counts[0].1;
+        counts.into_iter()
+              .take_while(|&(_, count)| count == min)
+              .map(|(v, _)| v)
+              .collect()
+    }
+
+    /// Tally the frequency of every distinct value in the buffer, sorted
+    /// in descending order of count.
+    fn counts(&mut self) -> Vec<(T, uint)> {
+        self.sort();
+        let mut counts: Vec<(T, uint)> = vec![];
+        for partial in self.data.iter() {
+            let v = &partial.0;
+            match counts.last_mut() {
+                Some(&(ref last_v, ref mut count)) if *last_v == *v => {
+                    *count += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            counts.push((v.clone(), 1));
+        }
+        counts.sort_by(|a, b| b.1.cmp(&a.1));
+        counts
+    }
+}
+
+impl<T: PartialOrd + ToPrimitive> Unsorted<T> {
+    /// Returns the median absolute deviation (MAD) of the data: the
+    /// median of the absolute deviations from the median.
+    ///
+    /// This is a robust measure of dispersion that plays a similar role
+    /// to `stddev` on `OnlineStats`, but is far less sensitive to
+    /// outliers.
+    ///
+    /// Returns `None` when there is no data.
+    pub fn mad(&mut self) -> Option<f64> {
+        self.mad_opt(false)
+    }
+
+    /// Like `mad`, but scales the result by `1.4826` so that it
+    /// consistently estimates the standard deviation of normally
+    /// distributed data.
+    pub fn mad_scaled(&mut self) -> Option<f64> {
+        self.mad_opt(true)
+    }
+
+    fn mad_opt(&mut self, scaled: bool) -> Option<f64> {
+        let m = match self.median() {
+            None => return None,
+            Some(m) => m,
+        };
+        let mut devs: Unsorted<f64> = self.data.iter()
+            .map(|p| (p.0.to_f64().unwrap() - m).abs())
+            .collect();
+        devs.median().map(|mad| if scaled { mad * 1.4826 } else { mad })
+    }
+}
+
+/// Compute the median of an already-sorted slice of `Partial` values.
+fn median_on_sorted<T: ToPrimitive>(data: &[Partial<T>]) -> f64 {
+    let n = data.len();
+    if n % 2 == 0 {
+        let lo = data[n / 2 - 1].0.to_f64().unwrap();
+        let hi = data[n / 2].0.to_f64().unwrap();
+        (lo + hi) / 2.0
+    } else {
+        data[n / 2].0.to_f64().unwrap()
+    }
+}
+
+impl<T: PartialOrd> Commute for Unsorted<T> {
+    fn merge(&mut self, other: Unsorted<T>) {
+        self.dirtied();
+        let mut other = other;
+        self.data.extend(mem::replace(&mut other.data, Vec::new()).into_iter());
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for Unsorted<T> {
+    fn from_iter<I: Iterator<T>>(it: I) -> Unsorted<T> {
+        let mut v = Unsorted::new();
+        v.extend(it);
+        v
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for Unsorted<T> {
+    fn extend<I: Iterator<T>>(&mut self, it: I) {
+        self.dirtied();
+        self.data.extend(it.map(Partial));
+    }
+}
+
+impl<T: PartialOrd> Default for Unsorted<T> {
+    fn default() -> Unsorted<T> {
+        Unsorted::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Unsorted;
+
+    #[test]
+    fn quartiles_empty() {
+        let mut v: Unsorted<uint> = vec![].into_iter().collect();
+        assert_eq!(v.quartiles(), None);
+    }
+
+    #[test]
+    fn quartiles_one() {
+        let mut v: Unsorted<uint> = vec![5u].into_iter().collect();
+        assert_eq!(v.quartiles(), Some((5.0, 5.0, 5.0)));
+    }
+
+    #[test]
+    fn quartiles_two() {
+        let mut v: Unsorted<uint> = vec![2u, 4].into_iter().collect();
+        assert_eq!(v.quartiles(), Some((2.0, 3.0, 4.0)));
+    }
+
+    #[test]
+    fn quartiles_eight() {
+        let mut v: Unsorted<uint> =
+            vec![1u, 2, 3, 4, 5, 6, 7, 8].into_iter().collect();
+        assert_eq!(v.quartiles(), Some((2.5, 4.5, 6.5)));
+    }
+
+    #[test]
+    fn percentile_edges() {
+        let mut v: Unsorted<uint> =
+            vec![1u, 2, 3, 4, 5, 6, 7, 8].into_iter().collect();
+        assert_eq!(v.percentile(0.0), Some(1.0));
+        assert_eq!(v.percentile(100.0), Some(8.0));
+    }
+
+    #[test]
+    fn percentile_may_disagree_with_quartiles() {
+        // `percentile` uses linear-rank interpolation (NIST method 7),
+        // while `quartiles` uses Tukey's hinges. The two are independent
+        // estimators and are not required to agree at 25/50/75.
+        let mut v: Unsorted<uint> =
+            vec![1u, 2, 3, 4, 5, 6, 7, 8].into_iter().collect();
+        assert_eq!(v.quartiles(), Some((2.5, 4.5, 6.5)));
+        assert_eq!(v.percentile(25.0), Some(2.75));
+        assert_eq!(v.percentile(50.0), Some(4.5));
+        assert_eq!(v.percentile(75.0), Some(6.25));
+    }
+
+    #[test]
+    fn mad_empty() {
+        let mut v: Unsorted<uint> = vec![].into_iter().collect();
+        assert_eq!(v.mad(), None);
+        assert_eq!(v.mad_scaled(), None);
+    }
+
+    #[test]
+    fn mad_basic() {
+        // median is 3; absolute deviations are [2, 1, 0, 1, 2], whose
+        // median is 1.
+        let mut v: Unsorted<uint> = vec![1u, 2, 3, 4, 5].into_iter().collect();
+        assert_eq!(v.mad(), Some(1.0));
+        assert_eq!(v.mad_scaled(), Some(1.4826));
+    }
+
+    #[test]
+    fn mode_empty() {
+        let mut v: Unsorted<uint> = vec![].into_iter().collect();
+        assert_eq!(v.mode(), None);
+        assert_eq!(v.modes(), vec![]);
+        assert_eq!(v.antimodes(), vec![]);
+    }
+
+    #[test]
+    fn mode_unique() {
+        let mut v: Unsorted<uint> = vec![1u, 1, 2, 3].into_iter().collect();
+        assert_eq!(v.mode(), Some(1));
+        assert_eq!(v.modes(), vec![1]);
+    }
+
+    #[test]
+    fn mode_tie_is_none() {
+        let mut v: Unsorted<uint> = vec![1u, 1, 2, 2, 3].into_iter().collect();
+        assert_eq!(v.mode(), None);
+        let mut modes = v.modes();
+        modes.sort();
+        assert_eq!(modes, vec![1, 2]);
+    }
+
+    #[test]
+    fn antimodes_basic() {
+        let mut v: Unsorted<uint> = vec![1u, 1, 1, 2, 3].into_iter().collect();
+        let mut antimodes = v.antimodes();
+        antimodes.sort();
+        assert_eq!(antimodes, vec![2, 3]);
+    }
+}